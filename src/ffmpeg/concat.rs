@@ -2,7 +2,7 @@ use crate::error::VideoEncodeError;
 use path_abs::{PathAbs, PathInfo};
 use std::fmt::Write as hi;
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{error, info, debug, instrument};
@@ -13,11 +13,193 @@ pub enum OutputError {
     Fs(String),
     MkvMergeFailed(String),
     Io(std::io::Error),
+    Ivf(String),
+}
+
+impl From<std::io::Error> for OutputError {
+    fn from(err: std::io::Error) -> Self {
+        OutputError::Io(err)
+    }
+}
+
+/// The backend used to stitch encoded segments back into a single output
+/// file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcatMethod {
+    FFmpeg,
+    MkvMerge,
+    Ivf,
+}
+
+impl std::str::FromStr for ConcatMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ffmpeg" => Ok(Self::FFmpeg),
+            "mkvmerge" => Ok(Self::MkvMerge),
+            "ivf" => Ok(Self::Ivf),
+            other => Err(format!(
+                "unknown concat method {other:?}, expected one of \"ffmpeg\", \"mkvmerge\", \"ivf\""
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ConcatMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::FFmpeg => "ffmpeg",
+            Self::MkvMerge => "mkvmerge",
+            Self::Ivf => "ivf",
+        })
+    }
+}
+
+/// Parsed contents of an IVF file header, as described in the VP8/VP9/AV1
+/// bitstream container spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IvfHeader {
+    fourcc: [u8; 4],
+    width: u16,
+    height: u16,
+    timebase_denominator: u32,
+    timebase_numerator: u32,
+    frame_count: u32,
+}
+
+const IVF_HEADER_LEN: usize = 32;
+const IVF_FRAME_HEADER_LEN: usize = 12;
+const IVF_MAGIC: &[u8; 4] = b"DKIF";
+
+fn read_ivf_header(file: &mut File) -> Result<IvfHeader, OutputError> {
+    let mut header = [0u8; IVF_HEADER_LEN];
+    file.read_exact(&mut header)?;
+
+    if &header[0..4] != IVF_MAGIC {
+        return Err(OutputError::Ivf(
+            "not an IVF file: missing \"DKIF\" magic".to_string(),
+        ));
+    }
+
+    let header_length = u16::from_le_bytes([header[6], header[7]]);
+    if header_length as usize != IVF_HEADER_LEN {
+        return Err(OutputError::Ivf(format!(
+            "unexpected IVF header length: {header_length}"
+        )));
+    }
+
+    Ok(IvfHeader {
+        fourcc: [header[8], header[9], header[10], header[11]],
+        width: u16::from_le_bytes([header[12], header[13]]),
+        height: u16::from_le_bytes([header[14], header[15]]),
+        timebase_denominator: u32::from_le_bytes([header[16], header[17], header[18], header[19]]),
+        timebase_numerator: u32::from_le_bytes([header[20], header[21], header[22], header[23]]),
+        frame_count: u32::from_le_bytes([header[24], header[25], header[26], header[27]]),
+    })
+}
+
+fn write_ivf_header(file: &mut File, header: &IvfHeader) -> Result<(), OutputError> {
+    let mut buf = [0u8; IVF_HEADER_LEN];
+    buf[0..4].copy_from_slice(IVF_MAGIC);
+    buf[4..6].copy_from_slice(&0u16.to_le_bytes()); // version
+    buf[6..8].copy_from_slice(&(IVF_HEADER_LEN as u16).to_le_bytes());
+    buf[8..12].copy_from_slice(&header.fourcc);
+    buf[12..14].copy_from_slice(&header.width.to_le_bytes());
+    buf[14..16].copy_from_slice(&header.height.to_le_bytes());
+    buf[16..20].copy_from_slice(&header.timebase_denominator.to_le_bytes());
+    buf[20..24].copy_from_slice(&header.timebase_numerator.to_le_bytes());
+    buf[24..28].copy_from_slice(&header.frame_count.to_le_bytes());
+    // bytes 28..32 are reserved
+
+    file.write_all(&buf)?;
+    Ok(())
+}
+
+/// Concatenates a set of IVF segments entirely in-process, without shelling
+/// out to `ffmpeg` or `mkvmerge`. Every segment must share the same codec
+/// FourCC and frame dimensions; each segment's own timestamps restart at 0,
+/// so they are rewritten with a cumulative offset to stay continuous across
+/// segment boundaries.
+fn concatenate_ivf_segments(segment_paths: &[PathBuf], output: &Path) -> Result<(), OutputError> {
+    let Some(first_path) = segment_paths.first() else {
+        return Err(OutputError::Ivf("no IVF segments to concatenate".to_string()));
+    };
+
+    let mut first_file =
+        File::open(first_path).map_err(|err| OutputError::Ivf(format!("{first_path:?}: {err}")))?;
+    let reference_header = read_ivf_header(&mut first_file)?;
+
+    let mut output_file = File::create(output)?;
+    write_ivf_header(&mut output_file, &reference_header)?;
+
+    // Each segment's own timestamps restart at 0, so a cumulative offset is
+    // added to carry them forward continuously across segment boundaries.
+    let mut cumulative_offset: u64 = 0;
+    let mut total_frames: u32 = 0;
+
+    for path in segment_paths {
+        let mut segment = File::open(path).map_err(|err| OutputError::Ivf(format!("{path:?}: {err}")))?;
+        let header = read_ivf_header(&mut segment)?;
+
+        if header.fourcc != reference_header.fourcc
+            || header.width != reference_header.width
+            || header.height != reference_header.height
+        {
+            return Err(OutputError::Ivf(format!(
+                "segment {path:?} does not match the codec/dimensions of {first_path:?}"
+            )));
+        }
+
+        let mut max_timestamp_in_segment: u64 = 0;
+        let mut frames_in_segment: u32 = 0;
+        let mut frame_header = [0u8; IVF_FRAME_HEADER_LEN];
+        loop {
+            match segment.read_exact(&mut frame_header) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(OutputError::Ivf(format!("{path:?}: {err}"))),
+            }
+
+            let payload_size = u32::from_le_bytes([
+                frame_header[0],
+                frame_header[1],
+                frame_header[2],
+                frame_header[3],
+            ]);
+            let timestamp = u64::from_le_bytes(frame_header[4..12].try_into().unwrap());
+            max_timestamp_in_segment = max_timestamp_in_segment.max(timestamp);
+
+            let mut payload = vec![0u8; payload_size as usize];
+            segment.read_exact(&mut payload)?;
+
+            let mut out_frame_header = [0u8; IVF_FRAME_HEADER_LEN];
+            out_frame_header[0..4].copy_from_slice(&payload_size.to_le_bytes());
+            out_frame_header[4..12].copy_from_slice(&(cumulative_offset + timestamp).to_le_bytes());
+            output_file.write_all(&out_frame_header)?;
+            output_file.write_all(&payload)?;
+
+            total_frames += 1;
+            frames_in_segment += 1;
+        }
+
+        // Only advance the offset if this segment actually contributed
+        // frames; an empty segment shouldn't open a timestamp gap.
+        if frames_in_segment > 0 {
+            cumulative_offset += max_timestamp_in_segment + 1;
+        }
+    }
+
+    output_file.seek(SeekFrom::Start(24))?;
+    output_file.write_all(&total_frames.to_le_bytes())?;
+
+    Ok(())
 }
 
 pub fn mkvmerge(
     temp_dir: &Path,
     output: &Path,
+    original_input: &Path,
     encoder_extension: &str,
     num_tasks: usize,
 ) -> Result<(), OutputError> {
@@ -55,6 +237,8 @@ pub fn mkvmerge(
     encode_dir.push("encoded");
 
     let output = PathAbs::new(output).map_err(|err| OutputError::Path(err.to_string()))?;
+    let original_input =
+        PathAbs::new(original_input).map_err(|err| OutputError::Path(err.to_string()))?;
 
     assert!(num_tasks != 0);
 
@@ -64,6 +248,7 @@ pub fn mkvmerge(
         encoder_extension,
         &fix_path(output.to_str().unwrap()),
         audio_file.as_deref(),
+        &fix_path(original_input),
     );
 
     let mut options_json =
@@ -91,12 +276,28 @@ pub fn mkvmerge(
     Ok(())
 }
 
-pub fn mkvmerge_options_json(num: usize, ext: &str, output: &str, audio: Option<&str>) -> String {
-    let mut file_string = String::with_capacity(64 + 12 * num);
+pub fn mkvmerge_options_json(
+    num: usize,
+    ext: &str,
+    output: &str,
+    audio: Option<&str>,
+    original_input: &str,
+) -> String {
+    let mut file_string = String::with_capacity(96 + 12 * num);
     write!(file_string, "[\"-o\", {output:?}").unwrap();
     if let Some(audio) = audio {
         write!(file_string, ", {audio:?}").unwrap();
     }
+    // Pull in subtitle streams, chapters, and attachments from the original
+    // source; `--no-video` drops its video track, already covered by the
+    // encoded chunks below. When a pre-extracted `audio_file` is also being
+    // muxed in, additionally drop the original's audio with `--no-audio` so
+    // the audio tracks aren't duplicated.
+    write!(file_string, ", \"--no-video\"").unwrap();
+    if audio.is_some() {
+        write!(file_string, ", \"--no-audio\"").unwrap();
+    }
+    write!(file_string, ", {original_input:?}").unwrap();
     file_string.push_str(", \"[\"");
     for i in 0..num {
         write!(file_string, ", \"encoded_chunk_{i}.{ext}\"").unwrap();
@@ -135,17 +336,141 @@ fn ffmpeg_mux(concat: String, input: String, output: String) -> Result<(), Outpu
     Ok(())
 }
 
+/// Stream parameters `ffprobe` reports for a segment's first video stream,
+/// used to make sure all segments were encoded compatibly before muxing.
+#[derive(Debug, PartialEq, Eq)]
+struct SegmentStreamInfo {
+    codec_name: String,
+    width: String,
+    height: String,
+    pix_fmt: String,
+    time_base: String,
+}
+
+fn probe_segment_stream(path: &Path) -> Result<SegmentStreamInfo, VideoEncodeError> {
+    let out = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=codec_name,width,height,pix_fmt,time_base",
+            "-of",
+            "csv=p=0",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|err| {
+            VideoEncodeError::Concatenation(format!("failed to run ffprobe on {path:?}: {err}"))
+        })?;
+
+    if !out.status.success() {
+        return Err(VideoEncodeError::Concatenation(format!(
+            "ffprobe failed on segment {path:?}: {}",
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let fields: Vec<&str> = stdout.trim().split(',').collect();
+    let [codec_name, width, height, pix_fmt, time_base] = fields[..] else {
+        return Err(VideoEncodeError::Concatenation(format!(
+            "unexpected ffprobe output for segment {path:?}: {stdout:?}"
+        )));
+    };
+
+    Ok(SegmentStreamInfo {
+        codec_name: codec_name.to_string(),
+        width: width.to_string(),
+        height: height.to_string(),
+        pix_fmt: pix_fmt.to_string(),
+        time_base: time_base.to_string(),
+    })
+}
+
+/// Verifies every segment shares the same codec, dimensions, pixel format,
+/// and timebase before they are concatenated, so a mismatched chunk is
+/// caught here instead of surfacing as a corrupt output on playback.
+fn validate_segment_stream_parameters(segment_paths: &[PathBuf]) -> Result<(), VideoEncodeError> {
+    let Some(first_path) = segment_paths.first() else {
+        return Ok(());
+    };
+
+    let reference = probe_segment_stream(first_path)?;
+
+    for path in &segment_paths[1..] {
+        let info = probe_segment_stream(path)?;
+        if info != reference {
+            return Err(VideoEncodeError::Concatenation(format!(
+                "segment {path:?} has different stream parameters than {first_path:?}: {info:?} != {reference:?}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Scans `encoded_dir` for `encoded_chunk_<n>.<ext>` files and returns them
+/// sorted by the numeric chunk index, regardless of the order the directory
+/// happened to yield them in (`fs::read_dir` makes no ordering guarantee).
+/// Any directory entry whose name does not follow that pattern is an error;
+/// such entries are not skipped.
+fn discover_sorted_segments(encoded_dir: &Path) -> Result<Vec<PathBuf>, VideoEncodeError> {
+    let read_dir = fs::read_dir(encoded_dir).map_err(|err| {
+        VideoEncodeError::Concatenation(format!(
+            "failed to read encoded segment directory {encoded_dir:?}: {err}"
+        ))
+    })?;
+
+    let mut indexed_segments = Vec::new();
+    for entry in read_dir {
+        let path = entry
+            .map_err(|err| {
+                VideoEncodeError::Concatenation(format!(
+                    "failed to read entry in {encoded_dir:?}: {err}"
+                ))
+            })?
+            .path();
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+        let rest = file_name.strip_prefix("encoded_chunk_").ok_or_else(|| {
+            VideoEncodeError::Concatenation(format!(
+                "segment filename {file_name:?} does not match the expected \"encoded_chunk_<n>.<ext>\" pattern"
+            ))
+        })?;
+        let index_str = rest.split('.').next().unwrap_or(rest);
+        let index: usize = index_str.parse().map_err(|_| {
+            VideoEncodeError::Concatenation(format!(
+                "segment filename {file_name:?} does not match the expected \"encoded_chunk_<n>.<ext>\" pattern"
+            ))
+        })?;
+
+        indexed_segments.push((index, path));
+    }
+
+    indexed_segments.sort_by_key(|(index, _)| *index);
+    Ok(indexed_segments.into_iter().map(|(_, path)| path).collect())
+}
+
 /// Concatenates video segments and adds back non-video streams.
-#[instrument(skip(segment_paths))]
+///
+/// Segments are not taken from the caller; they are discovered and sorted
+/// by numeric chunk index directly from `temp_dir`'s `encoded` directory, so
+/// ordering is guaranteed regardless of how a caller might have collected
+/// the paths (e.g. unsorted directory iteration).
+///
+/// With `ConcatMethod::Ivf` only the concatenated video segments are written;
+/// no streams from `original_input` are added back.
+#[instrument]
 pub fn concatenate_videos_and_copy_streams(
-    segment_paths: Vec<PathBuf>,
     original_input: &Path,
     output_file: &Path,
     temp_dir: &PathBuf,
     expected_segments: usize,
-    concat: &String,
+    concat: ConcatMethod,
 ) -> Result<(), VideoEncodeError> {
-    // Verify that all segments exist and match the expected count
+    let segment_paths = discover_sorted_segments(&temp_dir.join("encoded"))?;
     if segment_paths.len() != expected_segments {
         return Err(VideoEncodeError::Concatenation(format!(
             "Mismatch in segment count. Expected: {}, Found: {}",
@@ -154,33 +479,44 @@ pub fn concatenate_videos_and_copy_streams(
         )));
     }
 
-    for path in segment_paths.iter() {
-        if !path.exists() {
-            return Err(VideoEncodeError::Concatenation(format!(
-                "Segment file not found: {:?}",
-                path
-            )));
-        }
+    // The IVF backend already checks FourCC/width/height itself, in-process;
+    // skip the ffprobe-based check there so it keeps its no-external-binary
+    // guarantee instead of silently picking up a hard ffprobe dependency.
+    if concat != ConcatMethod::Ivf {
+        validate_segment_stream_parameters(&segment_paths)?;
     }
 
-    let temp_file_list = PathBuf::from("file_list.txt");
-    let status = if concat == "ffmpeg" {
-        // Create a temporary file list for FFmpeg
-        // Unfortunately due to current implementation path of the files inside
-        // is relative to the file
-        let file_list_content: String = segment_paths
-            .iter()
-            .map(|path| format!("file '{}'\n", path.to_str().unwrap()))
-            .collect();
-        std::fs::write(&temp_file_list, file_list_content)?;
-    
-        let temp_st = temp_file_list.to_string_lossy();
-        let original_input = original_input.to_string_lossy();
-        let output_file = output_file.to_string_lossy();
+    // Only the ffmpeg backend writes this; track it so cleanup doesn't try
+    // to remove a file the other backends never created.
+    let mut temp_file_list: Option<PathBuf> = None;
+    let status = match concat {
+        ConcatMethod::FFmpeg => {
+            // Create a temporary file list for FFmpeg
+            // Unfortunately due to current implementation path of the files inside
+            // is relative to the file
+            let file_list_content: String = segment_paths
+                .iter()
+                .map(|path| format!("file '{}'\n", path.to_str().unwrap()))
+                .collect();
+            let file_list_path = PathBuf::from("file_list.txt");
+            std::fs::write(&file_list_path, file_list_content)?;
 
-        ffmpeg_mux(temp_st.into(), original_input.into(), output_file.into())
-    } else {
-        mkvmerge(&temp_dir, &output_file, "mkv".into(), expected_segments)
+            let temp_st = file_list_path.to_string_lossy();
+            let original_input = original_input.to_string_lossy();
+            let output_file = output_file.to_string_lossy();
+
+            let result = ffmpeg_mux(temp_st.into(), original_input.into(), output_file.into());
+            temp_file_list = Some(file_list_path);
+            result
+        }
+        ConcatMethod::Ivf => concatenate_ivf_segments(&segment_paths, output_file),
+        ConcatMethod::MkvMerge => mkvmerge(
+            &temp_dir,
+            &output_file,
+            original_input,
+            "mkv".into(),
+            expected_segments,
+        ),
     };
 
     if status.is_err() {
@@ -196,8 +532,204 @@ pub fn concatenate_videos_and_copy_streams(
         segment_paths.len(),
     );
 
-    // Clean up temporary file
-    fs::remove_file(temp_file_list)?;
+    // Clean up the ffmpeg file list, if one was written
+    if let Some(temp_file_list) = temp_file_list {
+        fs::remove_file(temp_file_list)?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("concat_rs_test_{}_{name}", std::process::id()))
+    }
+
+    fn write_ivf_segment(path: &Path, fourcc: &[u8; 4], width: u16, height: u16, frames: &[(u64, &[u8])]) {
+        let mut file = File::create(path).unwrap();
+        write_ivf_header(
+            &mut file,
+            &IvfHeader {
+                fourcc: *fourcc,
+                width,
+                height,
+                timebase_denominator: 1,
+                timebase_numerator: 30,
+                frame_count: frames.len() as u32,
+            },
+        )
+        .unwrap();
+
+        for (timestamp, payload) in frames {
+            let mut frame_header = [0u8; IVF_FRAME_HEADER_LEN];
+            frame_header[0..4].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+            frame_header[4..12].copy_from_slice(&timestamp.to_le_bytes());
+            file.write_all(&frame_header).unwrap();
+            file.write_all(payload).unwrap();
+        }
+    }
+
+    #[test]
+    fn mkvmerge_options_json_drops_only_video_when_no_audio_file_was_extracted() {
+        let json = mkvmerge_options_json(1, "mkv", "out.mkv", None, "source.mkv");
+        assert_eq!(
+            json,
+            "[\"-o\", \"out.mkv\", \"--no-video\", \"source.mkv\", \"[\", \"encoded_chunk_0.mkv\",\"]\"]"
+        );
+    }
+
+    #[test]
+    fn mkvmerge_options_json_also_drops_audio_when_an_audio_file_was_extracted() {
+        let json = mkvmerge_options_json(1, "mkv", "out.mkv", Some("audio.mkv"), "source.mkv");
+        assert_eq!(
+            json,
+            "[\"-o\", \"out.mkv\", \"audio.mkv\", \"--no-video\", \"--no-audio\", \"source.mkv\", \"[\", \"encoded_chunk_0.mkv\",\"]\"]"
+        );
+    }
+
+    fn read_ivf_frames(path: &Path) -> (IvfHeader, Vec<(u64, Vec<u8>)>) {
+        let mut file = File::open(path).unwrap();
+        let header = read_ivf_header(&mut file).unwrap();
+
+        let mut frames = Vec::new();
+        let mut frame_header = [0u8; IVF_FRAME_HEADER_LEN];
+        loop {
+            match file.read_exact(&mut frame_header) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(err) => panic!("unexpected read error: {err}"),
+            }
+            let size = u32::from_le_bytes(frame_header[0..4].try_into().unwrap());
+            let timestamp = u64::from_le_bytes(frame_header[4..12].try_into().unwrap());
+            let mut payload = vec![0u8; size as usize];
+            file.read_exact(&mut payload).unwrap();
+            frames.push((timestamp, payload));
+        }
+
+        (header, frames)
+    }
+
+    #[test]
+    fn ivf_header_round_trips() {
+        let path = temp_path("header_roundtrip.ivf");
+        let header = IvfHeader {
+            fourcc: *b"AV01",
+            width: 1920,
+            height: 1080,
+            timebase_denominator: 1,
+            timebase_numerator: 30,
+            frame_count: 7,
+        };
+
+        let mut file = File::create(&path).unwrap();
+        write_ivf_header(&mut file, &header).unwrap();
+        drop(file);
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(read_ivf_header(&mut file).unwrap(), header);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn concatenate_ivf_segments_offsets_timestamps_cumulatively() {
+        let seg0 = temp_path("seg0.ivf");
+        let seg1 = temp_path("seg1.ivf");
+        let out = temp_path("out.ivf");
+
+        // seg0 has a gap (0, 2) like a show-existing-frame chunk would;
+        // seg1's timestamps restart at 0 and must be carried forward past it.
+        write_ivf_segment(&seg0, b"AV01", 64, 64, &[(0, b"aa"), (2, b"bb")]);
+        write_ivf_segment(&seg1, b"AV01", 64, 64, &[(0, b"cc")]);
+
+        concatenate_ivf_segments(&[seg0.clone(), seg1.clone()], &out).unwrap();
+
+        let (header, frames) = read_ivf_frames(&out);
+        assert_eq!(header.frame_count, 3);
+        let timestamps: Vec<u64> = frames.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(timestamps, vec![0, 2, 3]);
+        let payloads: Vec<&[u8]> = frames.iter().map(|(_, payload)| payload.as_slice()).collect();
+        assert_eq!(payloads, vec![b"aa".as_slice(), b"bb".as_slice(), b"cc".as_slice()]);
+
+        fs::remove_file(&seg0).unwrap();
+        fs::remove_file(&seg1).unwrap();
+        fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn concatenate_ivf_segments_skips_offset_for_empty_segment() {
+        let seg0 = temp_path("empty_seg0.ivf");
+        let seg1 = temp_path("empty_seg1.ivf");
+        let seg2 = temp_path("empty_seg2.ivf");
+        let out = temp_path("empty_out.ivf");
+
+        write_ivf_segment(&seg0, b"AV01", 64, 64, &[(0, b"aa"), (1, b"bb")]);
+        write_ivf_segment(&seg1, b"AV01", 64, 64, &[]); // no frames: shouldn't open a gap
+        write_ivf_segment(&seg2, b"AV01", 64, 64, &[(0, b"cc")]);
+
+        concatenate_ivf_segments(&[seg0.clone(), seg1.clone(), seg2.clone()], &out).unwrap();
+
+        let (header, frames) = read_ivf_frames(&out);
+        assert_eq!(header.frame_count, 3);
+        let timestamps: Vec<u64> = frames.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(timestamps, vec![0, 1, 2]);
+
+        fs::remove_file(&seg0).unwrap();
+        fs::remove_file(&seg1).unwrap();
+        fs::remove_file(&seg2).unwrap();
+        fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn concatenate_ivf_segments_rejects_mismatched_dimensions() {
+        let seg0 = temp_path("mismatch_seg0.ivf");
+        let seg1 = temp_path("mismatch_seg1.ivf");
+        let out = temp_path("mismatch_out.ivf");
+
+        write_ivf_segment(&seg0, b"AV01", 64, 64, &[(0, b"aa")]);
+        write_ivf_segment(&seg1, b"AV01", 32, 32, &[(0, b"bb")]);
+
+        let result = concatenate_ivf_segments(&[seg0.clone(), seg1.clone()], &out);
+        assert!(matches!(result, Err(OutputError::Ivf(_))));
+
+        fs::remove_file(&seg0).unwrap();
+        fs::remove_file(&seg1).unwrap();
+        fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn discover_sorted_segments_orders_numerically() {
+        let dir = temp_path("encoded_ordered");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["encoded_chunk_10.ivf", "encoded_chunk_2.ivf", "encoded_chunk_1.ivf"] {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        let discovered = discover_sorted_segments(&dir).unwrap();
+        let names: Vec<String> = discovered
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["encoded_chunk_1.ivf", "encoded_chunk_2.ivf", "encoded_chunk_10.ivf"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn discover_sorted_segments_rejects_unexpected_filenames() {
+        let dir = temp_path("encoded_bad_name");
+        fs::create_dir_all(&dir).unwrap();
+        File::create(dir.join("not_a_chunk.ivf")).unwrap();
+
+        let err = discover_sorted_segments(&dir).unwrap_err();
+        assert!(matches!(err, VideoEncodeError::Concatenation(_)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}